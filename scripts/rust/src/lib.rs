@@ -1,10 +1,17 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::fs;
 use std::io;
 use std::io::BufRead;
+use std::io::Read as _;
+use std::io::Write;
 use std::path::Path;
+use std::path::PathBuf;
 use std::rc::Rc;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+use std::time::UNIX_EPOCH;
 
 // The output is wrapped in a Result to allow matching on errors
 // Returns an Iterator to the Reader of the lines of the file.
@@ -16,7 +23,46 @@ where
     Ok(io::BufReader::new(file).lines())
 }
 
+/// Derives `(class_id, instance_id)` from a metadata line of the form
+/// `.../class_id/instance_id/dir/file`, with exactly one directory level
+/// between `instance_id` and the file. Returns `None` for blank lines or
+/// lines too shallow to contain both directory levels, rather than
+/// panicking - callers should skip such lines.
+fn parse_class_and_instance(line: &str) -> Option<(String, String)> {
+    let p = Path::new(line);
+    let instance_id_dir = p.parent()?.parent()?;
+    let instance_id = instance_id_dir.file_name()?.to_str()?;
+    let class_id = instance_id_dir.parent()?.file_name()?.to_str()?;
+    Some((class_id.to_string(), instance_id.to_string()))
+}
+
+fn insert_instance(
+    categories: &mut HashMap<String, Rc<HashSet<String>>>,
+    class_id: &str,
+    instance_id: &str,
+) {
+    if categories.contains_key(class_id) {
+        let mut st_rc = categories.get(class_id).unwrap().clone();
+        if !st_rc.contains(instance_id) {
+            categories.remove(class_id);
+            Rc::get_mut(&mut st_rc)
+                .unwrap()
+                .insert(instance_id.to_string());
+            categories.insert(class_id.to_string(), st_rc);
+        }
+    } else {
+        let mut st = HashSet::new();
+        st.insert(instance_id.to_string());
+        categories.insert(class_id.to_string(), Rc::new(st));
+    }
+}
+
 /// {category: {instance_id: filepath, ...}, ...}}
+///
+/// Kept as a standalone sequential implementation rather than a thin
+/// wrapper over `calc_categories_parallel`: spinning up worker threads
+/// for metadata files small enough to read in one pass would cost more
+/// than it saves. Use `calc_categories_parallel` directly for large files.
 pub fn calc_categories<P>(meta_data_file: P) -> HashMap<String, Rc<HashSet<String>>>
 where
     P: AsRef<Path>,
@@ -24,37 +70,1340 @@ where
     let mut categories: HashMap<String, Rc<HashSet<String>>> = HashMap::new();
 
     if let Ok(lines) = read_lines(meta_data_file) {
-        for line in lines {
-            if let Ok(line) = line {
-                let p = Path::new(&line);
-                let instance_id_dir = p.parent().unwrap().parent().unwrap();
-                let instance_id = instance_id_dir.file_name().unwrap().to_str().unwrap();
-                let class_id = instance_id_dir
-                    .parent()
-                    .unwrap()
-                    .file_name()
-                    .unwrap()
-                    .to_str()
-                    .unwrap();
-
-                let mut st: HashSet<String>;
-                if categories.contains_key(class_id) {
-                    let mut st_rc = categories.get(class_id).unwrap().clone();
-                    if !st_rc.contains(instance_id) {
-                        categories.remove(class_id);
-                        Rc::get_mut(&mut st_rc)
-                            .unwrap()
-                            .insert(instance_id.to_string());
-                        categories.insert(class_id.to_string(), st_rc);
+        for line in lines.map_while(Result::ok) {
+            if let Some((class_id, instance_id)) = parse_class_and_instance(&line) {
+                insert_instance(&mut categories, &class_id, &instance_id);
+            }
+        }
+    }
+
+    categories
+}
+
+// ---------------------------------------------------------------------
+// Glob-filtered ingestion
+//
+// Training often needs to index only a subset of instances (a
+// train/val/test split, or a single synset). `calc_categories_filtered`
+// matches each metadata line's path against shell-style glob patterns
+// (`*` and `?`) and skips lines that don't survive the filter *before*
+// the parent-directory/class-id parsing runs.
+//
+// This crate has no dependencies (there's no Cargo.toml wiring one in), so
+// patterns are compiled to a small token sequence instead of an actual
+// regex. Unlike a shell glob, `*` here matches across `/` separators
+// (e.g. `*bar` matches `foo/bar`) - callers relying on separator-aware
+// globbing should scope patterns accordingly.
+// ---------------------------------------------------------------------
+
+#[derive(Clone, Copy)]
+enum GlobToken {
+    Star,
+    Any,
+    Lit(char),
+}
+
+/// A glob pattern compiled once into a token sequence, so repeated
+/// matching against many lines doesn't re-parse the pattern text.
+struct CompiledGlob {
+    tokens: Vec<GlobToken>,
+}
+
+fn compile_glob(pattern: &str) -> CompiledGlob {
+    let tokens = pattern
+        .chars()
+        .map(|c| match c {
+            '*' => GlobToken::Star,
+            '?' => GlobToken::Any,
+            c => GlobToken::Lit(c),
+        })
+        .collect();
+    CompiledGlob { tokens }
+}
+
+/// Anchored match of `text` against a compiled glob, using the classic
+/// two-pointer wildcard-matching backtrack algorithm.
+fn glob_matches(compiled: &CompiledGlob, text: &str) -> bool {
+    let tokens = &compiled.tokens;
+    let chars: Vec<char> = text.chars().collect();
+
+    let (mut ti, mut ci) = (0usize, 0usize);
+    let mut star_ti: Option<usize> = None;
+    let mut star_ci: usize = 0;
+
+    while ci < chars.len() {
+        if ti < tokens.len() {
+            match tokens[ti] {
+                GlobToken::Lit(c) if c == chars[ci] => {
+                    ti += 1;
+                    ci += 1;
+                    continue;
+                }
+                GlobToken::Any => {
+                    ti += 1;
+                    ci += 1;
+                    continue;
+                }
+                GlobToken::Star => {
+                    star_ti = Some(ti);
+                    star_ci = ci;
+                    ti += 1;
+                    continue;
+                }
+                _ => {}
+            }
+        }
+        if let Some(sti) = star_ti {
+            ti = sti + 1;
+            star_ci += 1;
+            ci = star_ci;
+        } else {
+            return false;
+        }
+    }
+
+    while ti < tokens.len() && matches!(tokens[ti], GlobToken::Star) {
+        ti += 1;
+    }
+    ti == tokens.len()
+}
+
+fn compile_globs(patterns: &[String]) -> Vec<CompiledGlob> {
+    patterns.iter().map(|p| compile_glob(p)).collect()
+}
+
+fn matches_any(compiled: &[CompiledGlob], text: &str) -> bool {
+    compiled.iter().any(|g| glob_matches(g, text))
+}
+
+/// Same result as `calc_categories`, but restricted to metadata lines
+/// whose full path matches at least one of `include` (or all lines, if
+/// `include` is empty) and none of `exclude`.
+pub fn calc_categories_filtered<P>(
+    meta_data_file: P,
+    include: &[String],
+    exclude: &[String],
+) -> HashMap<String, Rc<HashSet<String>>>
+where
+    P: AsRef<Path>,
+{
+    let mut categories: HashMap<String, Rc<HashSet<String>>> = HashMap::new();
+
+    let include_globs = compile_globs(include);
+    let exclude_globs = compile_globs(exclude);
+
+    if let Ok(lines) = read_lines(meta_data_file) {
+        for line in lines.map_while(Result::ok) {
+            if !include_globs.is_empty() && !matches_any(&include_globs, &line) {
+                continue;
+            }
+            if matches_any(&exclude_globs, &line) {
+                continue;
+            }
+
+            if let Some((class_id, instance_id)) = parse_class_and_instance(&line) {
+                insert_instance(&mut categories, &class_id, &instance_id);
+            }
+        }
+    }
+
+    categories
+}
+
+// ---------------------------------------------------------------------
+// Cached ingestion
+//
+// `calc_categories` walks the whole metadata file on every call, which is
+// wasteful for ShapeNet-scale lists with hundreds of thousands of lines.
+// `load_categories_cached` keeps an in-process LRU of already-parsed
+// indices and backs it with an on-disk index file next to the metadata
+// file, so a full re-parse only happens when the metadata file actually
+// changed (as judged by its size and mtime).
+// ---------------------------------------------------------------------
+
+/// `{class_id: {instance_id, ...}}`, as returned by `calc_categories`.
+type CategoryIndex = HashMap<String, Rc<HashSet<String>>>;
+
+/// Suffix appended to a metadata file's path to get its on-disk index path.
+const CACHE_INDEX_SUFFIX: &str = ".catindex";
+
+fn index_path_for<P: AsRef<Path>>(meta_data_file: P) -> PathBuf {
+    let mut s = meta_data_file.as_ref().as_os_str().to_os_string();
+    s.push(CACHE_INDEX_SUFFIX);
+    PathBuf::from(s)
+}
+
+/// (file size in bytes, mtime in seconds since the epoch)
+fn metadata_fingerprint<P: AsRef<Path>>(path: P) -> Option<(u64, u64)> {
+    let meta = fs::metadata(path).ok()?;
+    let size = meta.len();
+    let mtime = meta
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    Some((size, mtime))
+}
+
+fn write_index_file(
+    index_path: &Path,
+    fingerprint: (u64, u64),
+    categories: &CategoryIndex,
+) -> io::Result<()> {
+    let mut out = String::new();
+    out.push_str(&format!("size={}\n", fingerprint.0));
+    out.push_str(&format!("mtime={}\n", fingerprint.1));
+    for (class_id, instances) in categories {
+        let instances_joined = instances
+            .iter()
+            .cloned()
+            .collect::<Vec<String>>()
+            .join(",");
+        out.push_str(&format!("{}\t{}\n", class_id, instances_joined));
+    }
+
+    let mut file = fs::File::create(index_path)?;
+    file.write_all(out.as_bytes())
+}
+
+fn read_index_file(index_path: &Path) -> io::Result<((u64, u64), CategoryIndex)> {
+    let contents = fs::read_to_string(index_path)?;
+    let mut lines = contents.lines();
+
+    let size = lines
+        .next()
+        .and_then(|l| l.strip_prefix("size="))
+        .and_then(|v| v.parse::<u64>().ok())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing size header"))?;
+    let mtime = lines
+        .next()
+        .and_then(|l| l.strip_prefix("mtime="))
+        .and_then(|v| v.parse::<u64>().ok())
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing mtime header"))?;
+
+    let mut categories: HashMap<String, Rc<HashSet<String>>> = HashMap::new();
+    for line in lines {
+        if line.is_empty() {
+            continue;
+        }
+        let mut parts = line.splitn(2, '\t');
+        let class_id = parts.next().unwrap_or_default().to_string();
+        let instances = parts
+            .next()
+            .unwrap_or_default()
+            .split(',')
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+            .collect::<HashSet<String>>();
+        categories.insert(class_id, Rc::new(instances));
+    }
+
+    Ok(((size, mtime), categories))
+}
+
+/// A small bounded LRU mapping metadata-file paths to already-parsed
+/// category indices, keyed on the metadata file's path string.
+///
+/// Entries are stored as plain `HashSet<String>` (not `Rc`-wrapped):
+/// `Rc` isn't `Send`, and this cache lives behind a `Mutex` in a
+/// `static`, so it needs to stay thread-safe. Callers get their own
+/// fresh `Rc`s wrapped around a clone of the cached sets.
+struct CategoryCache {
+    capacity: usize,
+    entries: HashMap<String, HashMap<String, HashSet<String>>>,
+    order: VecDeque<String>,
+}
+
+impl CategoryCache {
+    fn new(capacity: usize) -> Self {
+        CategoryCache {
+            capacity: capacity.max(1),
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Updates the capacity in place, evicting the oldest entries first if
+    /// the new capacity is smaller than the current entry count. This is
+    /// what lets every `load_categories_cached` call's `cache_capacity`
+    /// argument actually take effect, not just the very first one: the
+    /// cache itself is a process-global `OnceLock`, so without this the
+    /// capacity passed on the first call would silently apply forever.
+    fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity.max(1);
+        while self.entries.len() > self.capacity {
+            let Some(oldest) = self.order.pop_front() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+
+    fn touch(&mut self, key: &str) {
+        if let Some(pos) = self.order.iter().position(|k| k == key) {
+            self.order.remove(pos);
+        }
+        self.order.push_back(key.to_string());
+    }
+
+    fn get(&mut self, key: &str) -> Option<CategoryIndex> {
+        if self.entries.contains_key(key) {
+            self.touch(key);
+            self.entries.get(key).map(|categories| {
+                categories
+                    .iter()
+                    .map(|(class_id, instances)| (class_id.clone(), Rc::new(instances.clone())))
+                    .collect()
+            })
+        } else {
+            None
+        }
+    }
+
+    fn put(&mut self, key: String, value: &CategoryIndex) {
+        let at_capacity = !self.entries.contains_key(&key) && self.entries.len() >= self.capacity;
+        if let Some(oldest) = at_capacity.then(|| self.order.pop_front()).flatten() {
+            self.entries.remove(&oldest);
+        }
+        let plain: HashMap<String, HashSet<String>> = value
+            .iter()
+            .map(|(class_id, instances)| (class_id.clone(), (**instances).clone()))
+            .collect();
+        self.entries.insert(key.clone(), plain);
+        self.touch(&key);
+    }
+
+    fn invalidate(&mut self, key: &str) {
+        self.entries.remove(key);
+        if let Some(pos) = self.order.iter().position(|k| k == key) {
+            self.order.remove(pos);
+        }
+    }
+}
+
+static CACHE: OnceLock<Mutex<CategoryCache>> = OnceLock::new();
+
+/// Gets (initializing if needed) the process-global cache, and applies
+/// `cache_capacity` to it. The cache itself is a singleton shared across
+/// every distinct `meta_data_file` ever loaded, so `cache_capacity` isn't
+/// "this path's capacity" - it's "the capacity as of the most recent
+/// call", and takes effect immediately (including evicting entries) even
+/// though the `OnceLock` itself is only initialized once.
+fn cache(cache_capacity: usize) -> &'static Mutex<CategoryCache> {
+    let cache = CACHE.get_or_init(|| Mutex::new(CategoryCache::new(cache_capacity)));
+    cache.lock().unwrap().set_capacity(cache_capacity);
+    cache
+}
+
+/// Same result as `calc_categories`, but backed by an in-process bounded
+/// LRU and an on-disk index file next to `meta_data_file`. The on-disk
+/// index is validated against the metadata file's current size and mtime;
+/// a full parse only happens when no cached entry is valid.
+///
+/// The in-process LRU is a single process-wide cache shared by every
+/// metadata file, not one cache per path - `cache_capacity` sets its
+/// total entry count, and the most recently passed value wins across all
+/// callers (shrinking it evicts the oldest entries immediately).
+pub fn load_categories_cached<P>(meta_data_file: P, cache_capacity: usize) -> CategoryIndex
+where
+    P: AsRef<Path>,
+{
+    let path = meta_data_file.as_ref();
+    let key = path.to_string_lossy().to_string();
+    let cache_mutex = cache(cache_capacity);
+
+    if let Some(hit) = cache_mutex.lock().unwrap().get(&key) {
+        return hit;
+    }
+
+    let fingerprint = metadata_fingerprint(path);
+    let index_path = index_path_for(path);
+
+    let fresh_on_disk = fingerprint.and_then(|fingerprint| {
+        read_index_file(&index_path)
+            .ok()
+            .filter(|(cached_fingerprint, _)| *cached_fingerprint == fingerprint)
+    });
+    if let Some((_, categories)) = fresh_on_disk {
+        cache_mutex.lock().unwrap().put(key, &categories);
+        return categories;
+    }
+
+    let categories = calc_categories(path);
+    if let Some(fingerprint) = fingerprint {
+        let _ = write_index_file(&index_path, fingerprint, &categories);
+    }
+    cache_mutex.lock().unwrap().put(key, &categories);
+    categories
+}
+
+/// Drop any cached (in-memory and on-disk) index for `meta_data_file`.
+///
+/// Deliberately uses `CACHE.get()` rather than `cache(_)`: if nothing has
+/// called `load_categories_cached` yet there's no in-memory entry to drop
+/// and no capacity that should be established (a forced capacity here
+/// would stick, since the cache is a process-global singleton).
+pub fn invalidate<P>(meta_data_file: P)
+where
+    P: AsRef<Path>,
+{
+    let path = meta_data_file.as_ref();
+    let key = path.to_string_lossy().to_string();
+    if let Some(cache) = CACHE.get() {
+        cache.lock().unwrap().invalidate(&key);
+    }
+    let _ = fs::remove_file(index_path_for(path));
+}
+
+// ---------------------------------------------------------------------
+// Content-hash manifest
+//
+// Dataset directories get corrupted or silently duplicated across
+// synsets. A manifest maps `class_id/instance_id -> hash` of the
+// referenced files, so it can later be used to detect missing/changed
+// files (`verify_manifest`) or duplicated meshes (`find_duplicate_instances`).
+// ---------------------------------------------------------------------
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Fast, non-cryptographic FNV-1a hash, streamed over the file in fixed
+/// chunks so multi-gigabyte meshes don't need to be read into memory.
+fn hash_file<P: AsRef<Path>>(path: P) -> io::Result<u64> {
+    let mut file = fs::File::open(path)?;
+    let mut buf = [0u8; 64 * 1024];
+    let mut hash = FNV_OFFSET_BASIS;
+
+    loop {
+        let n = file.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        for &byte in &buf[..n] {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    Ok(hash)
+}
+
+/// Combines per-file hashes into one instance hash. Deliberately a
+/// commutative, associative operation (wrapping add) rather than a
+/// tighter mix like `(existing ^ next).wrapping_mul(FNV_PRIME)`: metadata
+/// lines for a multi-file instance aren't guaranteed to appear in a
+/// stable order, and an order-sensitive fold would make `build_manifest`
+/// (and therefore `verify_manifest`/`find_duplicate_instances`) report a
+/// spurious change or miss a duplicate whenever a dataset's line order
+/// shifts but its file contents don't.
+fn fold_hash(existing: u64, next: u64) -> u64 {
+    existing.wrapping_add(next)
+}
+
+/// `class_id/instance_id -> hash`, where an instance's hash is the fold
+/// of the hashes of every file referenced for that instance.
+pub fn build_manifest<P>(meta_data_file: P) -> HashMap<String, u64>
+where
+    P: AsRef<Path>,
+{
+    let mut manifest: HashMap<String, u64> = HashMap::new();
+
+    if let Ok(lines) = read_lines(meta_data_file) {
+        for line in lines.map_while(Result::ok) {
+            let Some((class_id, instance_id)) = parse_class_and_instance(&line) else {
+                continue;
+            };
+            let key = format!("{}/{}", class_id, instance_id);
+            if let Ok(file_hash) = hash_file(&line) {
+                manifest
+                    .entry(key)
+                    .and_modify(|h| *h = fold_hash(*h, file_hash))
+                    .or_insert(file_hash);
+            }
+        }
+    }
+
+    manifest
+}
+
+pub fn write_manifest<P>(manifest: &HashMap<String, u64>, manifest_path: P) -> io::Result<()>
+where
+    P: AsRef<Path>,
+{
+    let mut out = String::new();
+    for (key, hash) in manifest {
+        out.push_str(&format!("{}\t{:016x}\n", key, hash));
+    }
+    fs::File::create(manifest_path)?.write_all(out.as_bytes())
+}
+
+pub fn read_manifest<P>(manifest_path: P) -> io::Result<HashMap<String, u64>>
+where
+    P: AsRef<Path>,
+{
+    let contents = fs::read_to_string(manifest_path)?;
+    let mut manifest = HashMap::new();
+    for line in contents.lines() {
+        let parsed = line
+            .split_once('\t')
+            .and_then(|(key, hash_hex)| Some((key, u64::from_str_radix(hash_hex, 16).ok()?)));
+        if let Some((key, hash)) = parsed {
+            manifest.insert(key.to_string(), hash);
+        }
+    }
+    Ok(manifest)
+}
+
+/// Difference between a stored manifest and the dataset's current state.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ManifestReport {
+    /// Present in the stored manifest but no longer found on disk.
+    pub missing: Vec<String>,
+    /// Present in both, but the content hash no longer matches.
+    pub changed: Vec<String>,
+    /// Found on disk but not present in the stored manifest.
+    pub extra: Vec<String>,
+}
+
+/// Recomputes hashes for `meta_data_file` and compares them against the
+/// manifest previously written to `manifest_path` by `write_manifest`.
+pub fn verify_manifest<P1, P2>(meta_data_file: P1, manifest_path: P2) -> io::Result<ManifestReport>
+where
+    P1: AsRef<Path>,
+    P2: AsRef<Path>,
+{
+    let stored = read_manifest(manifest_path)?;
+    let current = build_manifest(meta_data_file);
+
+    let mut report = ManifestReport::default();
+
+    for (key, stored_hash) in &stored {
+        match current.get(key) {
+            None => report.missing.push(key.clone()),
+            Some(current_hash) if current_hash != stored_hash => report.changed.push(key.clone()),
+            Some(_) => {}
+        }
+    }
+    for key in current.keys() {
+        if !stored.contains_key(key) {
+            report.extra.push(key.clone());
+        }
+    }
+
+    Ok(report)
+}
+
+/// Groups `class_id/instance_id` keys whose file hashes collide, so
+/// duplicated meshes across categories can be pruned.
+pub fn find_duplicate_instances<P>(meta_data_file: P) -> HashMap<u64, Vec<String>>
+where
+    P: AsRef<Path>,
+{
+    let manifest = build_manifest(meta_data_file);
+
+    let mut by_hash: HashMap<u64, Vec<String>> = HashMap::new();
+    for (key, hash) in manifest {
+        by_hash.entry(hash).or_default().push(key);
+    }
+
+    by_hash.retain(|_, keys| keys.len() > 1);
+    by_hash
+}
+
+// ---------------------------------------------------------------------
+// COCO-style JSON ingestion
+//
+// `read_lines`/`calc_categories` only understand a newline-delimited list
+// of file paths. `calc_categories_coco` parses a COCO-style JSON
+// annotation file into the same category index instead. Because
+// annotation files can be large, it first scans the document once to
+// record the byte offsets of the top-level `images`, `categories` and
+// `annotations` sections (a "page map"), then decodes only those three
+// slices instead of the whole document.
+// ---------------------------------------------------------------------
+
+#[derive(Debug, Clone)]
+enum JsonValue {
+    Null,
+    Bool(#[allow(dead_code)] bool),
+    Num(f64),
+    Str(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    fn get(&self, key: &str) -> Option<&JsonValue> {
+        match self {
+            JsonValue::Object(fields) => fields.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::Str(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    fn as_id_string(&self) -> Option<String> {
+        match self {
+            JsonValue::Num(n) => Some(format!("{}", *n as i64)),
+            JsonValue::Str(s) => Some(s.clone()),
+            _ => None,
+        }
+    }
+
+    fn as_array(&self) -> Option<&[JsonValue]> {
+        match self {
+            JsonValue::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+struct JsonParser<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> JsonParser<'a> {
+    fn new(s: &'a str) -> Self {
+        JsonParser {
+            bytes: s.as_bytes(),
+            pos: 0,
+        }
+    }
+
+    fn skip_ws(&mut self) {
+        while self.pos < self.bytes.len() && (self.bytes[self.pos] as char).is_whitespace() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn parse_value(&mut self) -> Option<JsonValue> {
+        self.skip_ws();
+        match self.peek()? {
+            b'{' => self.parse_object(),
+            b'[' => self.parse_array(),
+            b'"' => self.parse_string().map(JsonValue::Str),
+            b't' => {
+                self.pos += 4;
+                Some(JsonValue::Bool(true))
+            }
+            b'f' => {
+                self.pos += 5;
+                Some(JsonValue::Bool(false))
+            }
+            b'n' => {
+                self.pos += 4;
+                Some(JsonValue::Null)
+            }
+            _ => self.parse_number(),
+        }
+    }
+
+    /// Reads a 4-hex-digit `\uXXXX` escape's code unit, advancing past it.
+    fn parse_hex4(&mut self) -> Option<u16> {
+        let digits = self.bytes.get(self.pos..self.pos + 4)?;
+        let value = u16::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()?;
+        self.pos += 4;
+        Some(value)
+    }
+
+    // Collects raw (possibly multi-byte UTF-8) bytes into a buffer and
+    // decodes once at the end, rather than casting each byte to `char`
+    // (which would mangle multi-byte UTF-8 sequences into Latin-1).
+    fn parse_string(&mut self) -> Option<String> {
+        if self.peek()? != b'"' {
+            return None;
+        }
+        self.pos += 1;
+        let mut out: Vec<u8> = Vec::new();
+        while let Some(c) = self.peek() {
+            match c {
+                b'"' => {
+                    self.pos += 1;
+                    return String::from_utf8(out).ok();
+                }
+                b'\\' => {
+                    self.pos += 1;
+                    match self.peek()? {
+                        b'n' => {
+                            out.push(b'\n');
+                            self.pos += 1;
+                        }
+                        b't' => {
+                            out.push(b'\t');
+                            self.pos += 1;
+                        }
+                        b'r' => {
+                            out.push(b'\r');
+                            self.pos += 1;
+                        }
+                        b'u' => {
+                            self.pos += 1;
+                            let unit = self.parse_hex4()?;
+                            let code_point = if (0xD800..=0xDBFF).contains(&unit)
+                                && self.bytes.get(self.pos) == Some(&b'\\')
+                                && self.bytes.get(self.pos + 1) == Some(&b'u')
+                            {
+                                self.pos += 2;
+                                let low = self.parse_hex4()?;
+                                0x10000 + ((unit as u32 - 0xD800) << 10) + (low as u32 - 0xDC00)
+                            } else {
+                                unit as u32
+                            };
+                            let ch = char::from_u32(code_point).unwrap_or(char::REPLACEMENT_CHARACTER);
+                            let mut buf = [0u8; 4];
+                            out.extend_from_slice(ch.encode_utf8(&mut buf).as_bytes());
+                        }
+                        other => {
+                            out.push(other);
+                            self.pos += 1;
+                        }
+                    }
+                }
+                _ => {
+                    out.push(c);
+                    self.pos += 1;
+                }
+            }
+        }
+        None
+    }
+
+    fn parse_number(&mut self) -> Option<JsonValue> {
+        let start = self.pos;
+        while let Some(c) = self.peek() {
+            if c.is_ascii_digit() || matches!(c, b'-' | b'+' | b'.' | b'e' | b'E') {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        let text = std::str::from_utf8(&self.bytes[start..self.pos]).ok()?;
+        text.parse::<f64>().ok().map(JsonValue::Num)
+    }
+
+    fn parse_array(&mut self) -> Option<JsonValue> {
+        self.pos += 1; // '['
+        let mut items = Vec::new();
+        self.skip_ws();
+        if self.peek() == Some(b']') {
+            self.pos += 1;
+            return Some(JsonValue::Array(items));
+        }
+        loop {
+            items.push(self.parse_value()?);
+            self.skip_ws();
+            match self.peek()? {
+                b',' => {
+                    self.pos += 1;
+                }
+                b']' => {
+                    self.pos += 1;
+                    break;
+                }
+                _ => return None,
+            }
+        }
+        Some(JsonValue::Array(items))
+    }
+
+    fn parse_object(&mut self) -> Option<JsonValue> {
+        self.pos += 1; // '{'
+        let mut fields = Vec::new();
+        self.skip_ws();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Some(JsonValue::Object(fields));
+        }
+        loop {
+            self.skip_ws();
+            let key = self.parse_string()?;
+            self.skip_ws();
+            if self.peek()? != b':' {
+                return None;
+            }
+            self.pos += 1;
+            let value = self.parse_value()?;
+            fields.push((key, value));
+            self.skip_ws();
+            match self.peek()? {
+                b',' => {
+                    self.pos += 1;
+                }
+                b'}' => {
+                    self.pos += 1;
+                    break;
+                }
+                _ => return None,
+            }
+        }
+        Some(JsonValue::Object(fields))
+    }
+}
+
+fn parse_json(s: &str) -> Option<JsonValue> {
+    JsonParser::new(s).parse_value()
+}
+
+/// Skips over one JSON string literal starting at `bytes[pos]` (which must
+/// be `"`), returning the index just past the closing quote.
+fn skip_json_string(bytes: &[u8], pos: usize) -> usize {
+    let mut i = pos + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => {
+                i += 1;
+                break;
+            }
+            _ => i += 1,
+        }
+    }
+    i
+}
+
+/// Returns the byte offset just past the JSON value starting at `start`
+/// (an object, array, string, or bare literal/number).
+fn skip_json_value(bytes: &[u8], start: usize) -> usize {
+    let n = bytes.len();
+    let mut i = start;
+    while i < n && (bytes[i] as char).is_whitespace() {
+        i += 1;
+    }
+    match bytes.get(i) {
+        Some(b'{') | Some(b'[') => {
+            let open = bytes[i];
+            let close = if open == b'{' { b'}' } else { b']' };
+            let mut depth = 0i32;
+            while i < n {
+                match bytes[i] {
+                    b'"' => {
+                        i = skip_json_string(bytes, i);
+                        continue;
+                    }
+                    c if c == open => depth += 1,
+                    c if c == close => {
+                        depth -= 1;
+                        if depth == 0 {
+                            i += 1;
+                            break;
+                        }
                     }
-                } else {
-                    st = HashSet::new();
-                    st.insert(instance_id.to_string());
-                    categories.insert(class_id.to_string(), Rc::new(st));
+                    _ => {}
                 }
+                i += 1;
+            }
+            i
+        }
+        Some(b'"') => skip_json_string(bytes, i),
+        _ => {
+            while i < n && !matches!(bytes[i], b',' | b'}' | b']') {
+                i += 1;
             }
+            i
+        }
+    }
+}
+
+/// Single pass over a COCO-style root JSON object recording the
+/// `[start, end)` byte range of each top-level value whose key is in
+/// `keys`, without decoding any of the values themselves.
+fn locate_top_level_sections(contents: &str, keys: &[&str]) -> HashMap<String, (usize, usize)> {
+    let bytes = contents.as_bytes();
+    let n = bytes.len();
+    let mut ranges = HashMap::new();
+
+    let mut i = 0;
+    while i < n && bytes[i] != b'{' {
+        i += 1;
+    }
+    if i >= n {
+        return ranges;
+    }
+    i += 1; // past root '{'
+
+    while i < n {
+        while i < n && (bytes[i] as char).is_whitespace() {
+            i += 1;
+        }
+        if i >= n || bytes[i] == b'}' {
+            break;
+        }
+        if bytes[i] != b'"' {
+            i += 1;
+            continue;
+        }
+        let key_start = i + 1;
+        let key_end_quote = skip_json_string(bytes, i);
+        let key = &contents[key_start..key_end_quote.saturating_sub(1)];
+        i = key_end_quote;
+        while i < n && (bytes[i] as char).is_whitespace() {
+            i += 1;
+        }
+        if i >= n || bytes[i] != b':' {
+            break;
+        }
+        i += 1;
+        while i < n && (bytes[i] as char).is_whitespace() {
+            i += 1;
+        }
+        let value_start = i;
+        let value_end = skip_json_value(bytes, i);
+        if keys.contains(&key) {
+            ranges.insert(key.to_string(), (value_start, value_end));
+        }
+        i = value_end;
+        while i < n && (bytes[i] as char).is_whitespace() {
+            i += 1;
+        }
+        if i < n && bytes[i] == b',' {
+            i += 1;
+        }
+    }
+
+    ranges
+}
+
+/// Calls `f` once per top-level element of the JSON array occupying
+/// `range` in `contents`, parsing (and dropping) one element at a time
+/// instead of collecting the whole array into a `Vec<JsonValue>`. This is
+/// what keeps `annotations` - typically the largest section of a COCO
+/// file, often far bigger than `images`/`categories` combined - from
+/// being fully materialized in memory at once.
+fn stream_array_elements<F: FnMut(JsonValue)>(contents: &str, range: (usize, usize), mut f: F) {
+    let bytes = contents.as_bytes();
+    let (start, end) = range;
+    let mut i = start;
+    while i < end && (bytes[i] as char).is_whitespace() {
+        i += 1;
+    }
+    if i >= end || bytes[i] != b'[' {
+        return;
+    }
+    i += 1;
+
+    loop {
+        while i < end && (bytes[i] as char).is_whitespace() {
+            i += 1;
         }
+        if i >= end || bytes[i] == b']' {
+            break;
+        }
+        let elem_start = i;
+        let elem_end = skip_json_value(bytes, i);
+        if let Some(value) = parse_json(&contents[elem_start..elem_end]) {
+            f(value);
+        }
+        i = elem_end;
+        while i < end && (bytes[i] as char).is_whitespace() {
+            i += 1;
+        }
+        if i < end && bytes[i] == b',' {
+            i += 1;
+        }
+    }
+}
+
+/// Parses a COCO-style JSON annotation file into the same
+/// `{class_id: {instance_id, ...}}` index `calc_categories` produces,
+/// mapping `categories[].name` to class ids and `images[].file_name` to
+/// instance ids via the `annotations[].{category_id,image_id}` links.
+///
+/// `categories` and `images` are small relative to `annotations` in a
+/// typical COCO file, so they're decoded into lookup maps up front; only
+/// sections other than these three are skipped entirely. `annotations`
+/// itself is streamed element-by-element via `stream_array_elements`
+/// rather than collected into a `Vec<JsonValue>`, so the index is built
+/// without holding the whole annotations array in memory at once.
+pub fn calc_categories_coco<P>(json_file: P) -> HashMap<String, Rc<HashSet<String>>>
+where
+    P: AsRef<Path>,
+{
+    let mut categories: HashMap<String, Rc<HashSet<String>>> = HashMap::new();
+
+    let contents = match fs::read_to_string(json_file) {
+        Ok(c) => c,
+        Err(_) => return categories,
+    };
+
+    let sections = locate_top_level_sections(&contents, &["categories", "images", "annotations"]);
+
+    let category_names: HashMap<String, String> = sections
+        .get("categories")
+        .and_then(|(s, e)| parse_json(&contents[*s..*e]))
+        .and_then(|v| v.as_array().map(|items| items.to_vec()))
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|item| {
+            let id = item.get("id")?.as_id_string()?;
+            let name = item.get("name")?.as_str()?.to_string();
+            Some((id, name))
+        })
+        .collect();
+
+    let image_file_names: HashMap<String, String> = sections
+        .get("images")
+        .and_then(|(s, e)| parse_json(&contents[*s..*e]))
+        .and_then(|v| v.as_array().map(|items| items.to_vec()))
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|item| {
+            let id = item.get("id")?.as_id_string()?;
+            let file_name = item.get("file_name")?.as_str()?.to_string();
+            Some((id, file_name))
+        })
+        .collect();
+
+    if let Some(range) = sections.get("annotations").copied() {
+        stream_array_elements(&contents, range, |annotation| {
+            let category_id = match annotation.get("category_id").and_then(|v| v.as_id_string()) {
+                Some(id) => id,
+                None => return,
+            };
+            let image_id = match annotation.get("image_id").and_then(|v| v.as_id_string()) {
+                Some(id) => id,
+                None => return,
+            };
+            let class_id = match category_names.get(&category_id) {
+                Some(name) => name,
+                None => return,
+            };
+            let instance_id = match image_file_names.get(&image_id) {
+                Some(file_name) => file_name,
+                None => return,
+            };
+            insert_instance(&mut categories, class_id, instance_id);
+        });
     }
 
     categories
 }
+
+// ---------------------------------------------------------------------
+// Parallel ingestion
+//
+// For multi-hundred-thousand-line metadata files, `calc_categories`'s
+// sequential `read_lines` iterator with per-line parsing is a bottleneck.
+// `calc_categories_parallel` reads the whole file once with a single
+// sized allocation, splits it into line ranges, parses each range on its
+// own worker thread into a partial map, and merges the partials. The
+// merge is a plain set union keyed by class id, so the result doesn't
+// depend on how the lines were split across threads.
+// ---------------------------------------------------------------------
+
+/// Same result as `calc_categories`, parsed across `num_threads` worker
+/// threads instead of sequentially. `calc_categories` remains the plain
+/// single-threaded entry point; this is the scale-up variant for large
+/// metadata files.
+pub fn calc_categories_parallel<P>(
+    meta_data_file: P,
+    num_threads: usize,
+) -> HashMap<String, Rc<HashSet<String>>>
+where
+    P: AsRef<Path>,
+{
+    let contents = match fs::read_to_string(meta_data_file) {
+        Ok(c) => c,
+        Err(_) => return HashMap::new(),
+    };
+    let lines: Vec<&str> = contents.lines().collect();
+    if lines.is_empty() {
+        return HashMap::new();
+    }
+
+    let num_threads = num_threads.max(1);
+    let chunk_size = lines.len().div_ceil(num_threads).max(1);
+
+    let partials: Vec<HashMap<String, HashSet<String>>> = std::thread::scope(|scope| {
+        let handles: Vec<_> = lines
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    let mut local: HashMap<String, HashSet<String>> = HashMap::new();
+                    for line in chunk {
+                        if let Some((class_id, instance_id)) = parse_class_and_instance(line) {
+                            local.entry(class_id).or_default().insert(instance_id);
+                        }
+                    }
+                    local
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+
+    let mut merged: HashMap<String, HashSet<String>> = HashMap::new();
+    for partial in partials {
+        for (class_id, instances) in partial {
+            merged.entry(class_id).or_default().extend(instances);
+        }
+    }
+
+    merged
+        .into_iter()
+        .map(|(class_id, instances)| (class_id, Rc::new(instances)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU32;
+    use std::sync::atomic::Ordering;
+
+    static TEST_COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    /// A process- and call-unique path under the system temp dir, so
+    /// parallel test runs don't collide with each other.
+    fn temp_path(name: &str) -> PathBuf {
+        let id = TEST_COUNTER.fetch_add(1, Ordering::SeqCst);
+        std::env::temp_dir().join(format!("p2m_test_{}_{}_{}", std::process::id(), id, name))
+    }
+
+    fn write_lines(path: &Path, lines: &[&str]) {
+        let mut file = fs::File::create(path).unwrap();
+        for line in lines {
+            writeln!(file, "{}", line).unwrap();
+        }
+    }
+
+    #[test]
+    fn calc_categories_parallel_matches_sequential() {
+        let path = temp_path("metadata.txt");
+        let lines: Vec<String> = (0..200)
+            .map(|i| format!("/data/class_{}/instance_{}/models/model.obj", i % 7, i))
+            .collect();
+        let line_refs: Vec<&str> = lines.iter().map(|s| s.as_str()).collect();
+        write_lines(&path, &line_refs);
+
+        let sequential = calc_categories(&path);
+        for num_threads in [1, 2, 3, 8] {
+            let parallel = calc_categories_parallel(&path, num_threads);
+            assert_eq!(sequential.len(), parallel.len());
+            for (class_id, instances) in &sequential {
+                let parallel_instances = parallel.get(class_id).expect("class present");
+                assert_eq!(instances.as_ref(), parallel_instances.as_ref());
+            }
+        }
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn coco_round_trip_handles_non_ascii_file_names() {
+        let path = temp_path("annotations.json");
+        let json = r#"{
+            "images": [{"id": 1, "file_name": "café.jpg"}],
+            "categories": [{"id": 10, "name": "mug"}],
+            "annotations": [{"image_id": 1, "category_id": 10}]
+        }"#;
+        fs::write(&path, json).unwrap();
+
+        let categories = calc_categories_coco(&path);
+        let instances = categories.get("mug").expect("category present");
+        assert!(instances.contains("café.jpg"));
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn load_categories_cached_reuses_and_invalidates() {
+        let path = temp_path("cached_metadata.txt");
+        write_lines(&path, &["/data/class_a/instance_1/models/model.obj"]);
+
+        let first = load_categories_cached(&path, 4);
+        assert!(first.contains_key("class_a"));
+        assert!(!first.contains_key("class_longer_name"));
+
+        // Re-reading without touching the file should return the same index.
+        let second = load_categories_cached(&path, 4);
+        let mut first_keys: Vec<&String> = first.keys().collect();
+        let mut second_keys: Vec<&String> = second.keys().collect();
+        first_keys.sort();
+        second_keys.sort();
+        assert_eq!(first_keys, second_keys);
+
+        // An in-process hit is served as-is without re-checking the
+        // fingerprint, so a file rewrite alone isn't picked up...
+        write_lines(
+            &path,
+            &["/data/class_longer_name/instance_1/models/model.obj"],
+        );
+        let stale = load_categories_cached(&path, 4);
+        assert!(stale.contains_key("class_a"));
+        assert!(!stale.contains_key("class_longer_name"));
+
+        // ...until the cache entry is explicitly invalidated, which drops
+        // both the in-memory entry and the on-disk index, so the next
+        // call does a fresh parse.
+        invalidate(&path);
+        let after_invalidate = load_categories_cached(&path, 4);
+        assert!(after_invalidate.contains_key("class_longer_name"));
+
+        fs::remove_file(&path).ok();
+        fs::remove_file(index_path_for(&path)).ok();
+    }
+
+    #[test]
+    fn calc_categories_filtered_applies_include_and_exclude() {
+        let path = temp_path("filtered_metadata.txt");
+        write_lines(
+            &path,
+            &[
+                "/data/class_a/instance_1/models/model.obj",
+                "/data/class_a/instance_2/models/model.obj",
+                "/data/class_b/instance_3/models/model.obj",
+            ],
+        );
+
+        // Empty `include` matches everything.
+        let all = calc_categories_filtered(&path, &[], &[]);
+        assert_eq!(all.len(), 2);
+
+        // Non-empty `include` restricts to matching lines only.
+        let only_a = calc_categories_filtered(&path, &["*class_a*".to_string()], &[]);
+        assert_eq!(only_a.len(), 1);
+        assert!(only_a.contains_key("class_a"));
+
+        // `exclude` drops matching lines even when `include` is empty.
+        let without_b = calc_categories_filtered(&path, &[], &["*class_b*".to_string()]);
+        assert!(without_b.contains_key("class_a"));
+        assert!(!without_b.contains_key("class_b"));
+
+        // `*` crosses `/`, unlike a shell glob: a pattern with no slash in
+        // it can still match a path with several directory levels.
+        let cross_separator =
+            calc_categories_filtered(&path, &["*data*model.obj".to_string()], &[]);
+        assert_eq!(cross_separator.len(), 2);
+
+        fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn verify_manifest_reports_missing_changed_and_extra() {
+        let base = temp_path("verify_root");
+        let keep_dir = base.join("class_a").join("inst_keep").join("models");
+        let change_dir = base.join("class_a").join("inst_change").join("models");
+        let remove_dir = base.join("class_b").join("inst_remove").join("models");
+        fs::create_dir_all(&keep_dir).unwrap();
+        fs::create_dir_all(&change_dir).unwrap();
+        fs::create_dir_all(&remove_dir).unwrap();
+
+        let keep_file = keep_dir.join("model.obj");
+        let change_file = change_dir.join("model.obj");
+        let remove_file = remove_dir.join("model.obj");
+        fs::write(&keep_file, b"keep").unwrap();
+        fs::write(&change_file, b"before").unwrap();
+        fs::write(&remove_file, b"gone soon").unwrap();
+
+        let meta_path = temp_path("verify_metadata.txt");
+        write_lines(
+            &meta_path,
+            &[
+                keep_file.to_str().unwrap(),
+                change_file.to_str().unwrap(),
+                remove_file.to_str().unwrap(),
+            ],
+        );
+
+        let manifest_path = temp_path("verify_manifest.tsv");
+        let manifest = build_manifest(&meta_path);
+        write_manifest(&manifest, &manifest_path).unwrap();
+
+        // Untouched: a fresh verify should report no differences.
+        let clean_report = verify_manifest(&meta_path, &manifest_path).unwrap();
+        assert_eq!(clean_report, ManifestReport::default());
+
+        // Mutate one file, delete another, and add a new instance.
+        fs::write(&change_file, b"after").unwrap();
+        fs::remove_file(&remove_file).unwrap();
+        let extra_dir = base.join("class_c").join("inst_extra").join("models");
+        fs::create_dir_all(&extra_dir).unwrap();
+        let extra_file = extra_dir.join("model.obj");
+        fs::write(&extra_file, b"new").unwrap();
+        write_lines(
+            &meta_path,
+            &[
+                keep_file.to_str().unwrap(),
+                change_file.to_str().unwrap(),
+                remove_file.to_str().unwrap(),
+                extra_file.to_str().unwrap(),
+            ],
+        );
+
+        let report = verify_manifest(&meta_path, &manifest_path).unwrap();
+        assert_eq!(report.changed, vec!["class_a/inst_change".to_string()]);
+        assert_eq!(report.missing, vec!["class_b/inst_remove".to_string()]);
+        assert_eq!(report.extra, vec!["class_c/inst_extra".to_string()]);
+
+        fs::remove_dir_all(&base).ok();
+        fs::remove_file(&meta_path).ok();
+        fs::remove_file(&manifest_path).ok();
+    }
+
+    #[test]
+    fn fold_hash_is_order_independent_for_multi_file_instances() {
+        let a = temp_path("order_a.bin");
+        let b = temp_path("order_b.bin");
+        fs::write(&a, b"first file contents").unwrap();
+        fs::write(&b, b"second file, different length").unwrap();
+
+        let hash_a = hash_file(&a).unwrap();
+        let hash_b = hash_file(&b).unwrap();
+        assert_eq!(fold_hash(hash_a, hash_b), fold_hash(hash_b, hash_a));
+
+        fs::remove_file(&a).ok();
+        fs::remove_file(&b).ok();
+    }
+
+    #[test]
+    fn find_duplicate_instances_groups_identical_content() {
+        let base = temp_path("dup_root");
+        let class_a_models = base.join("class_a").join("inst_1").join("models");
+        let class_b_models = base.join("class_b").join("inst_2").join("models");
+        let class_c_models = base.join("class_c").join("inst_3").join("models");
+        fs::create_dir_all(&class_a_models).unwrap();
+        fs::create_dir_all(&class_b_models).unwrap();
+        fs::create_dir_all(&class_c_models).unwrap();
+
+        let file_a = class_a_models.join("model.obj");
+        let file_b = class_b_models.join("model.obj");
+        let file_c = class_c_models.join("model.obj");
+        fs::write(&file_a, b"same content").unwrap();
+        fs::write(&file_b, b"same content").unwrap();
+        fs::write(&file_c, b"different content").unwrap();
+
+        let meta_path = temp_path("dup_metadata.txt");
+        write_lines(
+            &meta_path,
+            &[
+                file_a.to_str().unwrap(),
+                file_b.to_str().unwrap(),
+                file_c.to_str().unwrap(),
+            ],
+        );
+
+        let duplicates = find_duplicate_instances(&meta_path);
+        let groups: Vec<Vec<String>> = duplicates.into_values().collect();
+        assert_eq!(groups.len(), 1);
+        let mut group = groups.into_iter().next().unwrap();
+        group.sort();
+        assert_eq!(
+            group,
+            vec!["class_a/inst_1".to_string(), "class_b/inst_2".to_string()]
+        );
+
+        fs::remove_dir_all(&base).ok();
+        fs::remove_file(&meta_path).ok();
+    }
+}